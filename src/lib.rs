@@ -17,24 +17,63 @@ let incremented = arr![x + 1, for x in 0..10; len 10];
 
 let incremented_if_odd = arr![x + 1, for x in 0..10, if x % 2 == 1; len 10];
 // [None, Some(2), None, Some(4), None, Some(6), None, Some(8), None, Some(10)]
+
+let cartesian = arr![(x, y), for x in 0..2, for y in 0..3; len 6];
+// [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]
+
+let incremented_if_odd_else_zero = arr![x + 1, for x in 0..10, if x % 2 == 1, else 0; len 10];
+// [0, 2, 0, 4, 0, 6, 0, 8, 0, 10]
 # }
 ```
 This Rust adaption provides a familiar and performant interface for creating and
 modifying fixed-size arrays. `Option` types allow the use of filters even in cases where
 the number of unfiltered outputs is unknown at compile time -- without any dynamic
-allocations!
+allocations! If a sentinel value is more convenient than an `Option`, attach an
+`else` to the last condition to get a plain `[T; N]` back instead, as in
+`incremented_if_odd_else_zero` above.
 
 The `arr!` pattern is generally expressed as `f(x), for x in interable, if condition; len N`,
 where `f(x)` and `iterable` are any [statement](https://doc.rust-lang.org/reference/statements.html),
 `condition` is any statement that evaluates to a `bool`, and and `x` is any [pattern](https://doc.rust-lang.org/reference/patterns.html). Unlike Python, we must also provide a const `N` matching
 the length of the provided iterable in order to ensure the output can be sized at compile time.
+The `; len N` suffix can be omitted when `iterable` is itself an array: its length is
+already part of its type, so `N` is inferred and a size mismatch is a compile error
+rather than a runtime panic. This inferred form takes a single `for` clause with no
+`if` or `else`; filtered and chained comprehensions still need `; len N`.
+
+```rust
+# use arrcomp::arr;
+# fn main() {
+let doubled = arr![x * 2, for x in [1, 2, 3]];
+assert_eq!(doubled, [2, 4, 6]);
+# }
+```
 
 <div class="warning">
-Note that the extended <code>arr![f(x), for x in a, for a in b, ... for c in iterable]</code>
-syntax that Python supports is not yet supported. Nested comprehensions like
+The extended <code>arr![f(x), for x in a, for y in b, ... for z in iterable]</code>
+syntax that Python supports is also available: chaining two or more <code>for</code>
+clauses produces the flattened cartesian product of their sources, with <code>N</code>
+equal to the product of the individual source lengths. An <code>if</code> attached
+after any clause is ANDed together with every other condition in the chain and
+checked once all of the clauses' variables are bound, exactly as with a single
+generator. Nested comprehensions like
 <code>arr![arr![f(x) for x in outer] for outer in iterable]</code> work as expected.
 </div>
 
+A filtered `arr!` comprehension yields `[Option<T>; N]`, which forces downstream code
+to re-scan for `Some`. When you'd rather have the surviving values packed to the front
+of a dense array, use [`arrf!`] instead:
+
+```rust
+use arrcomp::arrf;
+
+# fn main() {
+let (count, packed) = arrf![x, for x in 0..10, if x % 3 == 0; len 10];
+assert_eq!(count, 4);
+assert_eq!(&packed[..count], &[0, 3, 6, 9]);
+# }
+```
+
 Why this crate?
 ===============
 
@@ -100,60 +139,239 @@ macro_rules! arr {
     ($ex:stmt, for $x:pat in $input:expr $(, if $cond:expr)+; len $len:expr) => {{
         let mut iter = $input.into_iter();
 
-        if $input.len() != $len {
-            let msg = &format!("Expected {} elements, got {}.", $len, $input.len());
-            panic!("{}", msg);
+        let result = std::array::from_fn::<_, $len, _>(|i| {
+            let $x = iter.next().unwrap_or_else(|| {
+                panic!("iterator produced fewer than {} elements (stopped at index {})", $len, i)
+            });
+            (true $(&& $cond)*).then(|| {$ex})
+        });
+
+        if iter.next().is_some() {
+            panic!("iterator produced more than {} elements", $len);
         }
 
-        std::array::from_fn::<_, $len, _>(|_| {
-            let $x = iter.next().unwrap_or_default();
-            (true $(&& $cond)*).then(|| {$ex})
-        })
-        // let mut count = 0;
-        // let results = std::array::from_fn::<_, $len, _>(|_| {
-        //         let $x = iter.next().unwrap_or_default();
-        //         (true $(&& $cond)*).then(|| {
-        //             count += 1;
-        //             $ex
-        //         })
-        //     });
-
-        // // Return only the number of valid results
-        // Iterate through and apply the condition
-        // let mut final_array: [<typeof({$ex})>; 0] = []; // Placeholder for the array
-        // for i in 0..$len {
-        //     let $x = iter.next().unwrap_or_default();
-        //     if true $(&& $cond)* {
-        //         final_array[i] = Some({$ex});
-        //         count += 1;
-        //     }
-        // }
-
-
-        // final_array
-        // results[..count]
+        result
     }};
 
     ($ex:stmt, for $x:pat in $input:expr; len $len:expr) => {{
         let mut iter = $input.into_iter();
 
-        if $input.len() != $len {
-            let msg = &format!("Expected {} elements, got {}.", $len, $input.len());
-            panic!("{}", msg);
+        let result = std::array::from_fn::<_, $len, _>(|i| {
+            let $x = iter.next().unwrap_or_else(|| {
+                panic!("iterator produced fewer than {} elements (stopped at index {})", $len, i)
+            });
+            $ex
+        });
+
+        if iter.next().is_some() {
+            panic!("iterator produced more than {} elements", $len);
         }
 
-        std::array::from_fn::<_, $len, _>(|_| {
-            let $x = iter.next().unwrap_or_default();
-            $ex
-        })
+        result
+    }};
+
+    // Panic if no expression is provided - otherwise the iteration does nothing.
+    (_, for $x:pat in $input:expr $(, if $cond:expr)*; len $len:expr) => {{
+        let msg = &format!("Comprehension cannot start with a placeholder ``_``");
+        panic!("{}", msg);
+    }};
+
+    // An `else` fallback turns a filtered comprehension into a plain `[T; N]`
+    // instead of `[Option<T>; N]`: `$ex` and `$default` must unify to the same type.
+    // The comma before `else` is required because macro_rules forbids an `expr`
+    // fragment from being followed directly by an arbitrary keyword.
+    ($ex:stmt, for $x:pat in $input:expr $(, if $cond:expr)+, else $default:stmt; len $len:expr) => {{
+        let mut iter = $input.into_iter();
+
+        let result = std::array::from_fn::<_, $len, _>(|i| {
+            let $x = iter.next().unwrap_or_else(|| {
+                panic!("iterator produced fewer than {} elements (stopped at index {})", $len, i)
+            });
+            if true $(&& $cond)* { $ex } else { $default }
+        });
+
+        if iter.next().is_some() {
+            panic!("iterator produced more than {} elements", $len);
+        }
+
+        result
+    }};
+
+    // Two or more chained `for` clauses with no `if` anywhere produce the flattened
+    // cartesian product of their sources as a plain `[T; N]`.
+    (
+        $ex:stmt,
+        for $x0:pat in $input0:expr,
+        for $x1:pat in $input1:expr
+        $(, for $xn:pat in $inputn:expr)*
+        ; len $len:expr
+    ) => {{
+        // `$input0`/`$input1`/`$inputn` are spliced into `__arr_cartesian!` exactly
+        // once below. `$input0` is evaluated a single time, so it may be a non-`Copy`,
+        // single-use source (e.g. `vec.into_iter()`); every later source sits inside
+        // the `flat_map` closure of the clause before it and is evaluated again for
+        // each element that clause yields. Length is checked the same way as the
+        // single-generator arms: fill from the iterator and panic on a shortfall or
+        // overrun, rather than pre-multiplying each source's `.len()` (which would
+        // require evaluating every source twice and restricts sources to
+        // `ExactSizeIterator`).
+        let mut __iter = $crate::__arr_cartesian!(
+            @gen
+            {$ex}
+            $x0 in $input0,
+            $x1 in $input1
+            $(, $xn in $inputn)*
+        );
+
+        let result = std::array::from_fn::<_, $len, _>(|i| {
+            __iter.next().unwrap_or_else(|| {
+                panic!("iterator produced fewer than {} elements (stopped at index {})", $len, i)
+            })
+        });
+
+        if __iter.next().is_some() {
+            panic!("iterator produced more than {} elements", $len);
+        }
+
+        result
+    }};
+
+    // Two or more chained `for` clauses produce the flattened cartesian product of
+    // their sources. Each clause may carry its own `if` conditions; every condition
+    // (regardless of which clause it follows) is ANDed together and checked once all
+    // of the clauses' variables are bound, wrapping the result in `Option` exactly as
+    // the single-generator arm above does.
+    (
+        $ex:stmt,
+        for $x0:pat in $input0:expr $(, if $cond0:expr)*,
+        for $x1:pat in $input1:expr $(, if $cond1:expr)*
+        $(, for $xn:pat in $inputn:expr $(, if $condn:expr)*)*
+        ; len $len:expr
+    ) => {{
+        // See the no-`if` arm above: the first source is evaluated once and each later
+        // source once per element of the clause before it, and the length is checked by filling and overrun-checking rather than
+        // pre-multiplying `.len()`s.
+        let mut __iter = $crate::__arr_cartesian!(
+            @gen
+            {(true $(&& $cond0)* $(&& $cond1)* $($(&& $condn)*)*).then(|| {$ex})}
+            $x0 in $input0,
+            $x1 in $input1
+            $(, $xn in $inputn)*
+        );
+
+        let result = std::array::from_fn::<_, $len, _>(|i| {
+            __iter.next().unwrap_or_else(|| {
+                panic!("iterator produced fewer than {} elements (stopped at index {})", $len, i)
+            })
+        });
+
+        if __iter.next().is_some() {
+            panic!("iterator produced more than {} elements", $len);
+        }
+
+        result
     }};
 
     // Panic if no expression is provided - otherwise the iteration does nothing.
+    // Must come before the arm below: `_` parses as a valid `stmt`, so if the
+    // generic arm were tried first it would always win and this one would never
+    // fire.
+    (_, for $x:pat in $input:expr) => {{
+        let msg = &format!("Comprehension cannot start with a placeholder ``_``");
+        panic!("{}", msg);
+    }};
+
+    // When the source is an array, its length is already part of its type, so the
+    // `; len N` suffix can be inferred instead of asserted at runtime: a size
+    // mismatch becomes a compile error rather than a panic.
+    ($ex:stmt, for $x:pat in $input:expr) => {
+        $crate::__arr_map_array($input, move |$x| {$ex})
+    };
+}
+
+/// Maps an array of known length `N` into another array of the same length,
+/// inferring `N` from the input's type. Backs the `; len N`-less form of [`arr!`].
+/// Not part of the public API.
+#[doc(hidden)]
+pub fn __arr_map_array<T, U, const N: usize>(input: [T; N], mut f: impl FnMut(T) -> U) -> [U; N] {
+    let mut iter = input.into_iter();
+    std::array::from_fn(|_| f(iter.next().unwrap()))
+}
+
+/// Packing variant of [`arr!`]: instead of `[Option<T>; N]` with holes where the
+/// filter rejected an element, `arrf!` returns `(usize, [T; N])` where the first
+/// `count` slots hold the surviving values in iteration order and the remainder are
+/// padded with `T::default()`. This gives an allocation-free analogue of
+/// `filter().collect()` without the `Option` ceremony.
+///
+/// `T` must implement `Default` so the unused tail of the array can be padded.
+#[macro_export]
+macro_rules! arrf {
+    // Panic if no expression is provided - otherwise the iteration does nothing.
+    // Must come before the arms below: `_` parses as a valid `stmt`, so if a
+    // generic arm were tried first it would always win and this one would never
+    // fire.
     (_, for $x:pat in $input:expr $(, if $cond:expr)*; len $len:expr) => {{
         let msg = &format!("Comprehension cannot start with a placeholder ``_``");
         panic!("{}", msg);
     }};
 
+    ($ex:stmt, for $x:pat in $input:expr $(, if $cond:expr)+; len $len:expr) => {{
+        let mut iter = $input.into_iter();
+
+        let mut count = 0usize;
+        let mut array: [_; $len] = std::array::from_fn(|_| Default::default());
+        for i in 0..$len {
+            let $x = iter.next().unwrap_or_else(|| {
+                panic!("iterator produced fewer than {} elements (stopped at index {})", $len, i)
+            });
+            if true $(&& $cond)* {
+                array[count] = { $ex };
+                count += 1;
+            }
+        }
+
+        if iter.next().is_some() {
+            panic!("iterator produced more than {} elements", $len);
+        }
+
+        (count, array)
+    }};
+
+    ($ex:stmt, for $x:pat in $input:expr; len $len:expr) => {{
+        let mut iter = $input.into_iter();
+
+        let array: [_; $len] = std::array::from_fn(|i| {
+            let $x = iter.next().unwrap_or_else(|| {
+                panic!("iterator produced fewer than {} elements (stopped at index {})", $len, i)
+            });
+            $ex
+        });
+
+        if iter.next().is_some() {
+            panic!("iterator produced more than {} elements", $len);
+        }
+
+        ($len as usize, array)
+    }};
+}
+
+/// Builds the nested `flat_map`/`map` iterator chain for two or more chained `for`
+/// clauses in [`arr!`]. Not part of the public API.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __arr_cartesian {
+    // Innermost clause: bind its variable and produce the final value.
+    (@gen {$ex:stmt} $x:pat in $input:expr) => {
+        $input.into_iter().map(move |$x| {$ex})
+    };
+
+    // More clauses remain: `flat_map` over this clause's source and recurse.
+    (@gen {$ex:stmt} $x:pat in $input:expr, $($rest:tt)+) => {
+        $input.into_iter().flat_map(move |$x| {
+            $crate::__arr_cartesian!(@gen {$ex} $($rest)+)
+        })
+    };
 }
 
 #[cfg(test)]
@@ -416,4 +634,197 @@ mod tests {
             arr![y, for (x, y) in pairs, if x as f64 > y + 1.0; len 5]
         );
     }
+
+    #[rstest]
+    fn test_cartesian_product_two_generators() {
+        assert_eq!(
+            arr![(x, y), for x in 0..2, for y in 0..3; len 6],
+            [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]
+        );
+    }
+
+    #[rstest]
+    fn test_cartesian_product_three_generators() {
+        assert_eq!(
+            arr![x + y + z, for x in 0..2, for y in 0..2, for z in 0..2; len 8],
+            [0, 1, 1, 2, 1, 2, 2, 3]
+        );
+    }
+
+    #[rstest]
+    fn test_cartesian_product_with_cond() {
+        assert_eq!(
+            arr![(x, y), for x in 0..2, for y in 0..3, if x != y; len 6],
+            [
+                None,
+                Some((0, 1)),
+                Some((0, 2)),
+                Some((1, 0)),
+                None,
+                Some((1, 2))
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_cartesian_product_destructuring_pattern() {
+        let pairs = [(1, 2), (3, 4)];
+        assert_eq!(
+            arr![a + b + c, for (a, b) in pairs, for c in 0..2; len 4],
+            [3, 4, 7, 8]
+        );
+    }
+
+    #[rstest]
+    #[should_panic(expected = "iterator produced fewer than 6 elements (stopped at index 4)")]
+    fn test_cartesian_product_wrong_len() {
+        let _ = arr![(x, y), for x in 0..2, for y in 0..2; len 6];
+    }
+
+    // Regression test: each generator's source used to be spliced twice (once for the
+    // `.len()` precondition, once into the iterator chain), which is a hard compile
+    // error for a non-`Copy`, single-use source like `Vec::into_iter()`.
+    #[rstest]
+    fn test_cartesian_product_single_use_source() {
+        let v = vec![1, 2, 3];
+        assert_eq!(
+            arr![(x, y), for x in v.into_iter(), for y in 0..2; len 6],
+            [(1, 0), (1, 1), (2, 0), (2, 1), (3, 0), (3, 1)]
+        );
+    }
+
+    // Same non-`ExactSizeIterator` restriction lifted from the single-generator arms:
+    // `scan` drops `ExactSizeIterator`, so this only works without a `.len()` check.
+    #[rstest]
+    fn test_cartesian_product_non_exact_size_iterator_input(nums: [i32; 5]) {
+        assert_eq!(
+            arr![
+                (x, y),
+                for x in nums.into_iter().scan(0, |_, x| Some(x)),
+                for y in 0..2;
+                len 10
+            ],
+            [
+                (nums[0], 0),
+                (nums[0], 1),
+                (nums[1], 0),
+                (nums[1], 1),
+                (nums[2], 0),
+                (nums[2], 1),
+                (nums[3], 0),
+                (nums[3], 1),
+                (nums[4], 0),
+                (nums[4], 1),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_arrf_packs_filtered_values(nums: [i32; 5]) {
+        assert_eq!(
+            arrf![x, for x in nums, if x > 0; len 5],
+            (3, [99, 5, 9, 0, 0])
+        );
+    }
+
+    #[rstest]
+    fn test_arrf_no_matches(nums: [i32; 5]) {
+        assert_eq!(arrf![x, for x in nums, if x > 1000; len 5], (0, [0; 5]));
+    }
+
+    #[rstest]
+    fn test_arrf_all_match(nums: [i32; 5], nums_plus_one: [i32; 5]) {
+        assert_eq!(
+            arrf![x + 1, for x in nums, if true; len 5],
+            (5, nums_plus_one)
+        );
+    }
+
+    #[rstest]
+    fn test_arrf_no_cond_keeps_everything(nums: [i32; 5]) {
+        assert_eq!(arrf![x, for x in nums; len 5], (5, nums));
+    }
+
+    #[rstest]
+    #[should_panic(expected = "Comprehension cannot start with a placeholder")]
+    fn test_arrf_placeholder_panics() {
+        arrf![_, for x in 0..5, if x > 0; len 5];
+    }
+
+    #[rstest]
+    fn test_non_exact_size_iterator_input(nums: [i32; 5]) {
+        // `scan` drops `ExactSizeIterator`, so this only works without a `.len()` check.
+        assert_eq!(
+            arr![x, for x in nums.into_iter().scan(0, |_, x| Some(x)); len 5],
+            nums
+        );
+    }
+
+    #[rstest]
+    fn test_non_exact_size_iterator_input_with_cond(nums: [i32; 5]) {
+        assert_eq!(
+            arr![x, for x in nums.into_iter().scan(0, |_, x| Some(x)), if x > 0; len 5],
+            nums.map(|x| if x > 0 { Some(x) } else { None })
+        );
+    }
+
+    #[rstest]
+    #[should_panic(expected = "iterator produced fewer than 5 elements")]
+    fn test_iterator_too_short_panics() {
+        let _ = arr![x, for x in 0..3; len 5];
+    }
+
+    #[rstest]
+    #[should_panic(expected = "iterator produced more than 5 elements")]
+    fn test_iterator_too_long_panics() {
+        let _ = arr![x, for x in 0..10; len 5];
+    }
+
+    #[rstest]
+    fn test_else_fallback(nums: [i32; 5]) {
+        assert_eq!(
+            arr![x, for x in nums, if x > 0, else -1; len 5],
+            nums.map(|x| if x > 0 { x } else { -1 })
+        );
+    }
+
+    #[rstest]
+    fn test_else_fallback_multiple_conds(nums: [i32; 5]) {
+        assert_eq!(
+            arr![x, for x in nums, if x > 0, if x % 2 == 1, else 0; len 5],
+            nums.map(|x| if x > 0 && x % 2 == 1 { x } else { 0 })
+        );
+    }
+
+    #[rstest]
+    fn test_else_fallback_statement(nums: [i32; 5]) {
+        assert_eq!(
+            arr![{let _ = x; 1}, for x in nums, if x > 0, else 0; len 5],
+            nums.map(|x| if x > 0 { 1 } else { 0 })
+        );
+    }
+
+    #[rstest]
+    fn test_inferred_len_from_array(nums: [i32; 5], nums_plus_one: [i32; 5]) {
+        assert_eq!(arr![x + 1, for x in nums], nums_plus_one);
+    }
+
+    #[rstest]
+    fn test_inferred_len_from_array_literal() {
+        assert_eq!(arr![x * 2, for x in [1, 2, 3]], [2, 4, 6]);
+    }
+
+    #[rstest]
+    fn test_inferred_len_destructuring_pattern(pairs: [(i32, f64); 5]) {
+        assert_eq!(
+            arr![x, for (x, _) in pairs],
+            arr![x, for (x, _) in pairs; len 5]
+        );
+    }
+
+    #[rstest]
+    #[should_panic(expected = "Comprehension cannot start with a placeholder")]
+    fn test_inferred_len_placeholder_panics() {
+        arr![_, for x in [1, 2, 3]];
+    }
 }